@@ -0,0 +1,104 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sql_types::PostgreSqlType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryEvent {
+    RecordsInserted(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError(String);
+
+pub type QueryResult = Result<QueryEvent, QueryError>;
+
+#[derive(Debug, Default)]
+pub struct QueryErrorBuilder {
+    message: Option<String>,
+}
+
+impl QueryErrorBuilder {
+    pub fn new() -> QueryErrorBuilder {
+        QueryErrorBuilder { message: None }
+    }
+
+    pub fn schema_does_not_exist(&mut self, schema_name: String) -> &mut Self {
+        self.message = Some(format!("schema {} does not exist", schema_name));
+        self
+    }
+
+    pub fn table_does_not_exist(&mut self, table_name: String) -> &mut Self {
+        self.message = Some(format!("table {} does not exist", table_name));
+        self
+    }
+
+    pub fn column_does_not_exist(&mut self, columns: Vec<String>) -> &mut Self {
+        self.message = Some(format!("columns {:?} do not exist", columns));
+        self
+    }
+
+    pub fn too_many_insert_expressions(&mut self) -> &mut Self {
+        self.message = Some("too many insert expressions".to_owned());
+        self
+    }
+
+    pub fn syntax_error(&mut self, message: String) -> &mut Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub fn feature_not_supported(&mut self, raw_sql_query: String) -> &mut Self {
+        self.message = Some(format!("not supported: {}", raw_sql_query));
+        self
+    }
+
+    pub fn out_of_range(&mut self, _pg_type: PostgreSqlType, column_name: &str, row_index: usize) -> &mut Self {
+        self.message = Some(format!("value out of range for column {} in row {}", column_name, row_index));
+        self
+    }
+
+    pub fn type_mismatch(&mut self, value: &str, _pg_type: PostgreSqlType, column_name: &str, row_index: usize) -> &mut Self {
+        self.message = Some(format!(
+            "type mismatch for column {} in row {}: {}",
+            column_name, row_index, value
+        ));
+        self
+    }
+
+    pub fn string_length_mismatch(&mut self, _pg_type: PostgreSqlType, len: u64, column_name: &str, row_index: usize) -> &mut Self {
+        self.message = Some(format!(
+            "value for column {} in row {} is longer than {} characters",
+            column_name, row_index, len
+        ));
+        self
+    }
+
+    pub fn not_null_violation(&mut self, column_name: &str, row_index: usize) -> &mut Self {
+        self.message = Some(format!("null value in column {} violates not-null constraint in row {}", column_name, row_index));
+        self
+    }
+
+    pub fn invalid_conflict_target(&mut self, columns: &[String]) -> &mut Self {
+        self.message = Some(format!(
+            "there is no unique or exclusion constraint matching the ON CONFLICT specification for columns {:?}",
+            columns
+        ));
+        self
+    }
+
+    pub fn build(&mut self) -> QueryError {
+        QueryError(self.message.take().unwrap_or_default())
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::Value;
+use std::{
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+#[derive(Debug, Clone)]
+pub enum Datum {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    DictCode(u32),
+}
+
+impl Datum {
+    pub fn from_null() -> Datum {
+        Datum::Null
+    }
+
+    pub fn from_bool(value: bool) -> Datum {
+        Datum::Bool(value)
+    }
+
+    pub fn from_i64(value: i64) -> Datum {
+        Datum::Int(value)
+    }
+
+    pub fn from_f64(value: f64) -> Datum {
+        Datum::Float(value)
+    }
+
+    pub fn from_string(value: String) -> Datum {
+        Datum::Str(value)
+    }
+
+    pub fn from_u32(code: u32) -> Datum {
+        Datum::DictCode(code)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Datum::Null)
+    }
+}
+
+impl fmt::Display for Datum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Datum::Null => write!(f, "NULL"),
+            Datum::Bool(v) => write!(f, "{}", v),
+            Datum::Int(v) => write!(f, "{}", v),
+            Datum::Float(v) => write!(f, "{}", v),
+            Datum::Str(v) => write!(f, "{}", v),
+            Datum::DictCode(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl PartialEq for Datum {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Datum::Null, Datum::Null) => true,
+            (Datum::Bool(a), Datum::Bool(b)) => a == b,
+            (Datum::Int(a), Datum::Int(b)) => a == b,
+            (Datum::Float(a), Datum::Float(b)) => a.to_bits() == b.to_bits(),
+            (Datum::Str(a), Datum::Str(b)) => a == b,
+            (Datum::DictCode(a), Datum::DictCode(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Datum {}
+
+impl Hash for Datum {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Datum::Null => 0u8.hash(state),
+            Datum::Bool(v) => {
+                1u8.hash(state);
+                v.hash(state);
+            }
+            Datum::Int(v) => {
+                2u8.hash(state);
+                v.hash(state);
+            }
+            Datum::Float(v) => {
+                3u8.hash(state);
+                v.to_bits().hash(state);
+            }
+            Datum::Str(v) => {
+                4u8.hash(state);
+                v.hash(state);
+            }
+            Datum::DictCode(v) => {
+                5u8.hash(state);
+                v.hash(state);
+            }
+        }
+    }
+}
+
+impl TryFrom<&Value> for Datum {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Datum, String> {
+        match value {
+            Value::Null => Ok(Datum::Null),
+            Value::Boolean(v) => Ok(Datum::Bool(*v)),
+            Value::Number(v) => v
+                .parse::<i64>()
+                .map(Datum::Int)
+                .or_else(|_| v.parse::<f64>().map(Datum::Float))
+                .map_err(|_| v.clone()),
+            Value::SingleQuotedString(v) => Ok(Datum::Str(v.clone())),
+            Value::Date(v) | Value::Timestamp(v) | Value::Time(v) => Ok(Datum::Str(v.clone())),
+            other => Err(other.to_string()),
+        }
+    }
+}
+
+// An opaque packed row; layout is only ever produced by pack and consumed by unpack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary(Vec<u8>);
+
+impl Binary {
+    pub fn with_data(data: Vec<u8>) -> Binary {
+        Binary(data)
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn pack(datums: &[Datum]) -> Binary {
+        let mut bytes = vec![];
+        for datum in datums {
+            match datum {
+                Datum::Null => bytes.push(0),
+                Datum::Bool(v) => {
+                    bytes.push(1);
+                    bytes.push(*v as u8);
+                }
+                Datum::Int(v) => {
+                    bytes.push(2);
+                    bytes.extend_from_slice(&v.to_be_bytes());
+                }
+                Datum::Float(v) => {
+                    bytes.push(3);
+                    bytes.extend_from_slice(&v.to_be_bytes());
+                }
+                Datum::Str(v) => {
+                    bytes.push(4);
+                    bytes.extend_from_slice(&(v.len() as u32).to_be_bytes());
+                    bytes.extend_from_slice(v.as_bytes());
+                }
+                Datum::DictCode(v) => {
+                    bytes.push(5);
+                    bytes.extend_from_slice(&v.to_be_bytes());
+                }
+            }
+        }
+        Binary(bytes)
+    }
+
+    pub fn unpack(binary: &Binary) -> Vec<Datum> {
+        let bytes = &binary.0;
+        let mut datums = vec![];
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let tag = bytes[pos];
+            pos += 1;
+            match tag {
+                0 => datums.push(Datum::Null),
+                1 => {
+                    datums.push(Datum::Bool(bytes[pos] != 0));
+                    pos += 1;
+                }
+                2 => {
+                    let mut buf = [0u8; 8];
+                    buf.copy_from_slice(&bytes[pos..pos + 8]);
+                    datums.push(Datum::Int(i64::from_be_bytes(buf)));
+                    pos += 8;
+                }
+                3 => {
+                    let mut buf = [0u8; 8];
+                    buf.copy_from_slice(&bytes[pos..pos + 8]);
+                    datums.push(Datum::Float(f64::from_be_bytes(buf)));
+                    pos += 8;
+                }
+                4 => {
+                    let mut len_buf = [0u8; 4];
+                    len_buf.copy_from_slice(&bytes[pos..pos + 4]);
+                    let len = u32::from_be_bytes(len_buf) as usize;
+                    pos += 4;
+                    let s = String::from_utf8_lossy(&bytes[pos..pos + len]).into_owned();
+                    datums.push(Datum::Str(s));
+                    pos += len;
+                }
+                5 => {
+                    let mut buf = [0u8; 4];
+                    buf.copy_from_slice(&bytes[pos..pos + 4]);
+                    datums.push(Datum::DictCode(u32::from_be_bytes(buf)));
+                    pos += 4;
+                }
+                _ => unreachable!("corrupt packed row"),
+            }
+        }
+        datums
+    }
+}
@@ -15,6 +15,7 @@
 use crate::{dml::ExpressionEvaluation, query::plan::TableInserts};
 use kernel::SystemResult;
 use protocol::{
+    bind::BoundParameter,
     results::{QueryErrorBuilder, QueryEvent},
     Sender,
 };
@@ -22,15 +23,48 @@ use representation::{Binary, Datum};
 use sql_types::ConstraintError;
 use sqlparser::ast::{DataType, Expr, Query, SetExpr, UnaryOperator, Value};
 use std::{
+    collections::HashMap,
     convert::TryFrom,
     str::FromStr,
     sync::{Arc, Mutex},
 };
 use storage::{backend::BackendStorage, frontend::FrontendStorage, ColumnDefinition, Row};
 
+// Maps a dictionary column's distinct string values to the codes actually packed into a row.
+struct ColumnDictionary {
+    code_by_value: HashMap<String, u32>,
+    value_by_code: Vec<String>,
+}
+
+impl ColumnDictionary {
+    fn code_for(&mut self, value: &str) -> u32 {
+        if let Some(code) = self.code_by_value.get(value) {
+            return *code;
+        }
+
+        let code = self.value_by_code.len() as u32;
+        self.value_by_code.push(value.to_owned());
+        self.code_by_value.insert(value.to_owned(), code);
+        code
+    }
+}
+
+pub(crate) enum OnConflictAction {
+    DoNothing,
+    DoUpdate(Vec<(String, Expr)>),
+}
+
+// A parsed `ON CONFLICT (target, ...) DO ...` clause, threaded in from the planner.
+pub(crate) struct OnConflict {
+    pub(crate) target_columns: Vec<String>,
+    pub(crate) action: OnConflictAction,
+}
+
 pub(crate) struct InsertCommand<'ic, P: BackendStorage> {
     raw_sql_query: &'ic str,
     table_inserts: TableInserts,
+    on_conflict: Option<OnConflict>,
+    parameters: Vec<BoundParameter>,
     storage: Arc<Mutex<FrontendStorage<P>>>,
     session: Arc<dyn Sender>,
 }
@@ -39,22 +73,97 @@ impl<'ic, P: BackendStorage> InsertCommand<'ic, P> {
     pub(crate) fn new(
         raw_sql_query: &'ic str,
         table_inserts: TableInserts,
+        on_conflict: Option<OnConflict>,
+        parameters: Vec<BoundParameter>,
         storage: Arc<Mutex<FrontendStorage<P>>>,
         session: Arc<dyn Sender>,
     ) -> InsertCommand<'ic, P> {
         InsertCommand {
             raw_sql_query,
             table_inserts,
+            on_conflict,
+            parameters,
             storage,
             session,
         }
     }
 
+    // Resolves a `$n` placeholder against the bound parameters, decoded via the target column's SQL type.
+    fn resolve_param(&self, placeholder: &str, column_definition: &ColumnDefinition) -> Result<Datum, ConstraintError> {
+        let index: usize = placeholder
+            .trim_start_matches('$')
+            .parse()
+            .map_err(|_| ConstraintError::TypeMismatch(placeholder.to_owned()))?;
+
+        // placeholders are 1-indexed; `$0` has no corresponding bound parameter.
+        if index < 1 {
+            return Err(ConstraintError::TypeMismatch(placeholder.to_owned()));
+        }
+
+        let bound = self
+            .parameters
+            .get(index - 1)
+            .ok_or_else(|| ConstraintError::TypeMismatch(placeholder.to_owned()))?;
+
+        let (validation_text, datum) = match bound {
+            BoundParameter::Text(text) => {
+                let datum = column_definition
+                    .sql_type()
+                    .decode(text.as_bytes())
+                    .map_err(|_| ConstraintError::TypeMismatch(text.clone()))?;
+                (text.clone(), datum)
+            }
+            BoundParameter::Binary(bytes) => {
+                let datum = column_definition
+                    .sql_type()
+                    .decode(bytes)
+                    .map_err(|_| ConstraintError::TypeMismatch(format!("{:?}", bytes)))?;
+                let text = datum.to_string();
+                (text, datum)
+            }
+        };
+
+        column_definition.sql_type().constraint().validate(&validation_text)?;
+
+        Ok(datum)
+    }
+
     pub(crate) fn execute(&mut self) -> SystemResult<()> {
         let table_name = self.table_inserts.table_id.name();
         let schema_name = self.table_inserts.table_id.schema_name();
         let Query { body, .. } = &*self.table_inserts.input;
         match &body {
+            SetExpr::Select(select) => {
+                let source_rows = match crate::query::plan::SelectInput::new(select.clone())
+                    .execute(self.storage.clone(), self.session.clone())
+                {
+                    Ok(rows) => rows,
+                    Err(()) => {
+                        self.session
+                            .send(Err(QueryErrorBuilder::new()
+                                .syntax_error(format!("could not execute source query for {}", self.raw_sql_query))
+                                .build()))
+                            .expect("To Send Query Result to Client");
+                        return Ok(());
+                    }
+                };
+
+                let columns = if self.table_inserts.column_indices.is_empty() {
+                    vec![]
+                } else {
+                    self.table_inserts
+                        .column_indices
+                        .clone()
+                        .into_iter()
+                        .map(|id| {
+                            let sqlparser::ast::Ident { value, .. } = id;
+                            value
+                        })
+                        .collect()
+                };
+
+                self.insert_rows_from_source(schema_name, table_name, columns, source_rows)
+            }
             SetExpr::Values(values) => {
                 let values = &values.0;
 
@@ -182,7 +291,23 @@ impl<'ic, P: BackendStorage> InsertCommand<'ic, P> {
                     index_cols
                 };
 
+                let mut dictionaries: HashMap<usize, ColumnDictionary> = HashMap::new();
+                for (index, column_definition) in all_columns.iter().enumerate() {
+                    if column_definition.is_dictionary_encoded() {
+                        let (code_by_value, value_by_code) =
+                            (self.storage.lock().unwrap()).load_dictionary(&schema_name, &table_name, column_definition.name())?;
+                        dictionaries.insert(
+                            index,
+                            ColumnDictionary {
+                                code_by_value,
+                                value_by_code,
+                            },
+                        );
+                    }
+                }
+
                 let mut to_write: Vec<Row> = vec![];
+                let mut staged: Vec<(Vec<u8>, Vec<Datum>)> = vec![];
                 if (self.storage.lock().unwrap()).table_exists(&schema_name, &table_name) {
                     let mut errors = Vec::new();
 
@@ -198,25 +323,92 @@ impl<'ic, P: BackendStorage> InsertCommand<'ic, P> {
 
                         let key = (self.storage.lock().unwrap()).next_key_id().to_be_bytes().to_vec();
 
-                        // TODO: The default value or NULL should be initialized for SQL types of all columns.
                         let mut record = vec![Datum::from_null(); all_columns.len()];
+                        let mut provided = vec![false; all_columns.len()];
                         for (item, (index, column_definition)) in row.iter().zip(index_columns.iter()) {
+                            provided[*index] = true;
+                            if let Value::Placeholder(marker) = item {
+                                match self.resolve_param(marker, column_definition) {
+                                    Ok(datum) => {
+                                        record[*index] = match dictionaries.get_mut(index) {
+                                            Some(dictionary) => Datum::from_u32(dictionary.code_for(datum.to_string().as_str())),
+                                            None => datum,
+                                        };
+                                    }
+                                    Err(e) => errors.push((e, column_definition.clone())),
+                                }
+                                continue;
+                            }
+
+                            if item == &Value::Null {
+                                record[*index] = Datum::from_null();
+                                continue;
+                            }
+
                             let v = match item.clone() {
                                 Value::Number(v) => v.to_string(),
                                 Value::SingleQuotedString(v) => v.to_string(),
                                 Value::Boolean(v) => v.to_string(),
-                                _ => unimplemented!("other types not implemented"),
+                                Value::Date(v) => v.to_string(),
+                                Value::Timestamp(v) => v.to_string(),
+                                Value::Time(v) => v.to_string(),
+                                other => {
+                                    self.session
+                                        .send(Err(QueryErrorBuilder::new()
+                                            .syntax_error(format!("literal {:?} is not currently supported", other))
+                                            .build()))
+                                        .expect("To Send Query Result to Client");
+                                    return Ok(());
+                                }
                             };
                             match column_definition.sql_type().constraint().validate(v.as_str()) {
-                                Ok(()) => {
-                                    record[*index] = Datum::try_from(item).unwrap();
-                                }
+                                Ok(()) => match dictionaries.get_mut(index) {
+                                    Some(dictionary) => record[*index] = Datum::from_u32(dictionary.code_for(v.as_str())),
+                                    None => match Datum::try_from(item) {
+                                        Ok(datum) => record[*index] = datum,
+                                        Err(value) => errors.push((ConstraintError::TypeMismatch(value), column_definition.clone())),
+                                    },
+                                },
                                 Err(e) => {
                                     errors.push((e, column_definition.clone()));
                                 }
                             }
                         }
 
+                        // Columns left untouched by this row: fill from their DEFAULT
+                        // expression if one is declared, otherwise leave them NULL.
+                        for (index, column_definition) in all_columns.iter().enumerate() {
+                            if provided[index] {
+                                continue;
+                            }
+
+                            if let Some(default_expr) = column_definition.default_value() {
+                                match evaluation.eval(default_expr) {
+                                    Ok(default_value) => match dictionaries.get_mut(&index) {
+                                        Some(dictionary) => record[index] = Datum::from_u32(dictionary.code_for(default_value.to_string().as_str())),
+                                        None => match Datum::try_from(&default_value) {
+                                            Ok(datum) => record[index] = datum,
+                                            Err(value) => errors.push((ConstraintError::TypeMismatch(value), column_definition.clone())),
+                                        },
+                                    },
+                                    Err(()) => return Ok(()),
+                                }
+                            }
+                        }
+
+                        // A NOT NULL column with neither a supplied value nor a DEFAULT
+                        // (or an explicit NULL) is a constraint violation.
+                        for (index, column_definition) in all_columns.iter().enumerate() {
+                            if errors.is_empty() && record[index].is_null() && !column_definition.is_nullable() {
+                                self.session
+                                    .send(Err(QueryErrorBuilder::new()
+                                        .not_null_violation(column_definition.name(), row_index + 1)
+                                        .build()))
+                                    .expect("To Send Query Result to Client");
+                                return Ok(());
+                            }
+                        }
+
                         // if there was an error then exit the loop.
                         if !errors.is_empty() {
                             // In SQL indexes start from 1, not 0.
@@ -259,13 +451,47 @@ impl<'ic, P: BackendStorage> InsertCommand<'ic, P> {
                             return Ok(());
                         }
 
-                        to_write.push((Binary::with_data(key), Binary::pack(&record)));
+                        staged.push((key, record));
                     }
                 }
 
-                match (self.storage.lock().unwrap()).insert_into(&schema_name, &table_name, to_write) {
+                if let Some(on_conflict) = self.on_conflict.as_ref() {
+                    staged = match self.resolve_on_conflict(&schema_name, &table_name, &all_columns, on_conflict, staged)
+                    {
+                        Ok(staged) => staged,
+                        Err(error) => {
+                            self.session
+                                .send(Err(error))
+                                .expect("To Send Query Result to Client");
+                            return Ok(());
+                        }
+                    };
+                }
+
+                let (index_writes, index_removals) = self.build_index_writes(&schema_name, &table_name, &staged)?;
+
+                for (key, record) in staged {
+                    to_write.push((Binary::with_data(key), Binary::pack(&record)));
+                }
+
+                // Dictionary codes are only persisted once the batch they belong to is
+                // known to have landed; flushing them earlier would leave codes visible
+                // for a write that never actually happened.
+                let inserted = (self.storage.lock().unwrap())
+                    .insert_into_with_indexes(&schema_name, &table_name, to_write, index_writes, index_removals);
+                match inserted {
                     Err(error) => Err(error),
                     Ok(size) => {
+                        for (index, dictionary) in dictionaries {
+                            let column_definition = &all_columns[index];
+                            (self.storage.lock().unwrap()).flush_dictionary(
+                                &schema_name,
+                                &table_name,
+                                column_definition.name(),
+                                &dictionary.value_by_code,
+                            )?;
+                        }
+
                         self.session
                             .send(Ok(QueryEvent::RecordsInserted(size)))
                             .expect("To Send Result to Client");
@@ -283,4 +509,554 @@ impl<'ic, P: BackendStorage> InsertCommand<'ic, P> {
             }
         }
     }
+
+    // Drives `INSERT INTO t (a, b) SELECT ...`: source_rows are matched positionally
+    // against columns and fed through the same validation pipeline as literal VALUES.
+    fn insert_rows_from_source(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        columns: Vec<String>,
+        source_rows: Vec<Vec<Datum>>,
+    ) -> SystemResult<()> {
+        if !(self.storage.lock().unwrap()).schema_exists(schema_name) {
+            self.session
+                .send(Err(QueryErrorBuilder::new().schema_does_not_exist(schema_name.to_owned()).build()))
+                .expect("To Send Result to Client");
+            return Ok(());
+        }
+
+        if !(self.storage.lock().unwrap()).table_exists(schema_name, table_name) {
+            self.session
+                .send(Err(QueryErrorBuilder::new()
+                    .table_does_not_exist(schema_name.to_owned() + "." + table_name)
+                    .build()))
+                .expect("To Send Result to Client");
+            return Ok(());
+        }
+
+        let all_columns = (self.storage.lock().unwrap()).table_columns(schema_name, table_name)?;
+        let index_columns: Vec<(usize, ColumnDefinition)> = if columns.is_empty() {
+            all_columns.iter().cloned().enumerate().collect()
+        } else {
+            let mut index_cols = vec![];
+            let mut non_existing_cols = vec![];
+            for column_name in columns {
+                match all_columns.iter().enumerate().find(|(_, c)| c.has_name(&column_name)) {
+                    Some((index, column_definition)) => index_cols.push((index, column_definition.clone())),
+                    None => non_existing_cols.push(column_name),
+                }
+            }
+
+            if !non_existing_cols.is_empty() {
+                self.session
+                    .send(Err(QueryErrorBuilder::new().column_does_not_exist(non_existing_cols).build()))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+
+            index_cols
+        };
+
+        let mut dictionaries: HashMap<usize, ColumnDictionary> = HashMap::new();
+        for (index, column_definition) in all_columns.iter().enumerate() {
+            if column_definition.is_dictionary_encoded() {
+                let (code_by_value, value_by_code) =
+                    (self.storage.lock().unwrap()).load_dictionary(schema_name, table_name, column_definition.name())?;
+                dictionaries.insert(
+                    index,
+                    ColumnDictionary {
+                        code_by_value,
+                        value_by_code,
+                    },
+                );
+            }
+        }
+
+        let evaluation = ExpressionEvaluation::new(self.session.clone());
+        let mut staged: Vec<(Vec<u8>, Vec<Datum>)> = vec![];
+        for (row_index, source_row) in source_rows.iter().enumerate() {
+            if source_row.len() != index_columns.len() {
+                self.session
+                    .send(Err(QueryErrorBuilder::new().too_many_insert_expressions().build()))
+                    .expect("To Send Result to Client");
+                return Ok(());
+            }
+
+            let key = (self.storage.lock().unwrap()).next_key_id().to_be_bytes().to_vec();
+            let mut record = vec![Datum::from_null(); all_columns.len()];
+            let mut provided = vec![false; all_columns.len()];
+            for (value, (index, column_definition)) in source_row.iter().zip(index_columns.iter()) {
+                provided[*index] = true;
+                if value.is_null() {
+                    continue;
+                }
+
+                match column_definition.sql_type().constraint().validate(value.to_string().as_str()) {
+                    Ok(()) => {
+                        record[*index] = match dictionaries.get_mut(index) {
+                            Some(dictionary) => Datum::from_u32(dictionary.code_for(value.to_string().as_str())),
+                            None => value.clone(),
+                        };
+                    }
+                    Err(e) => {
+                        let mut builder = QueryErrorBuilder::new();
+                        let pg_type = column_definition.sql_type().to_pg_types();
+                        match e {
+                            ConstraintError::OutOfRange => builder.out_of_range(pg_type, column_definition.name(), row_index + 1),
+                            ConstraintError::TypeMismatch(value) => {
+                                builder.type_mismatch(&value, pg_type, column_definition.name(), row_index + 1)
+                            }
+                            ConstraintError::ValueTooLong(len) => {
+                                builder.string_length_mismatch(pg_type, len, column_definition.name(), row_index + 1)
+                            }
+                        };
+                        self.session.send(Err(builder.build())).expect("To Send Result to Client");
+                        return Ok(());
+                    }
+                }
+            }
+
+            // Mirror the VALUES path: columns this row didn't target get their DEFAULT,
+            // then anything still NULL on a NOT NULL column is a constraint violation.
+            for (index, column_definition) in all_columns.iter().enumerate() {
+                if provided[index] {
+                    continue;
+                }
+
+                if let Some(default_expr) = column_definition.default_value() {
+                    match evaluation.eval(default_expr) {
+                        Ok(default_value) => match dictionaries.get_mut(&index) {
+                            Some(dictionary) => record[index] = Datum::from_u32(dictionary.code_for(default_value.to_string().as_str())),
+                            None => match Datum::try_from(&default_value) {
+                                Ok(datum) => record[index] = datum,
+                                Err(value) => {
+                                    self.session
+                                        .send(Err(QueryErrorBuilder::new()
+                                            .type_mismatch(&value, column_definition.sql_type().to_pg_types(), column_definition.name(), row_index + 1)
+                                            .build()))
+                                        .expect("To Send Result to Client");
+                                    return Ok(());
+                                }
+                            },
+                        },
+                        Err(()) => return Ok(()),
+                    }
+                }
+            }
+
+            for (index, column_definition) in all_columns.iter().enumerate() {
+                if record[index].is_null() && !column_definition.is_nullable() {
+                    self.session
+                        .send(Err(QueryErrorBuilder::new()
+                            .not_null_violation(column_definition.name(), row_index + 1)
+                            .build()))
+                        .expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+            }
+
+            staged.push((key, record));
+        }
+
+        if let Some(on_conflict) = self.on_conflict.as_ref() {
+            staged = match self.resolve_on_conflict(schema_name, table_name, &all_columns, on_conflict, staged) {
+                Ok(staged) => staged,
+                Err(error) => {
+                    self.session.send(Err(error)).expect("To Send Query Result to Client");
+                    return Ok(());
+                }
+            };
+        }
+
+        let (index_writes, index_removals) = self.build_index_writes(schema_name, table_name, &staged)?;
+
+        let to_write: Vec<Row> = staged
+            .into_iter()
+            .map(|(key, record)| (Binary::with_data(key), Binary::pack(&record)))
+            .collect();
+
+        let inserted =
+            (self.storage.lock().unwrap()).insert_into_with_indexes(schema_name, table_name, to_write, index_writes, index_removals);
+        match inserted {
+            Err(error) => Err(error),
+            Ok(size) => {
+                for (index, dictionary) in dictionaries {
+                    let column_definition = &all_columns[index];
+                    (self.storage.lock().unwrap()).flush_dictionary(
+                        schema_name,
+                        table_name,
+                        column_definition.name(),
+                        &dictionary.value_by_code,
+                    )?;
+                }
+
+                self.session
+                    .send(Ok(QueryEvent::RecordsInserted(size)))
+                    .expect("To Send Result to Client");
+                Ok(())
+            }
+        }
+    }
+
+    // Builds each declared index's entries for staged's rows, plus the stale entries to
+    // delete for any row whose indexed columns changed from what is currently stored.
+    fn build_index_writes(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        staged: &[(Vec<u8>, Vec<Datum>)],
+    ) -> SystemResult<(Vec<(String, Vec<Row>)>, Vec<(String, Vec<u8>)>)> {
+        let indexes = (self.storage.lock().unwrap()).table_indexes(schema_name, table_name)?;
+
+        let existing_rows: HashMap<Vec<u8>, Vec<Datum>> = (self.storage.lock().unwrap())
+            .table_scan(schema_name, table_name)?
+            .into_iter()
+            .map(|(key, packed)| (key.into_bytes(), Binary::unpack(&packed)))
+            .collect();
+
+        let mut index_writes = vec![];
+        let mut index_removals = vec![];
+        for index in indexes {
+            let mut entries = vec![];
+            for (key, record) in staged {
+                if let Some(old_record) = existing_rows.get(key) {
+                    let old_key_datums: Vec<Datum> = index.key_columns.iter().map(|i| old_record[*i].clone()).collect();
+                    let new_key_datums: Vec<Datum> = index.key_columns.iter().map(|i| record[*i].clone()).collect();
+                    if old_key_datums != new_key_datums {
+                        let mut old_index_key = Binary::pack(&old_key_datums).into_bytes();
+                        old_index_key.extend_from_slice(key);
+                        index_removals.push((index.name.clone(), old_index_key));
+                    }
+                }
+
+                let key_datums: Vec<Datum> = index.key_columns.iter().map(|i| record[*i].clone()).collect();
+                let mut index_key = Binary::pack(&key_datums).into_bytes();
+                index_key.extend_from_slice(key);
+
+                let include_datums: Vec<Datum> = index.include_columns.iter().map(|i| record[*i].clone()).collect();
+                entries.push((Binary::with_data(index_key), Binary::pack(&include_datums)));
+            }
+            index_writes.push((index.name, entries));
+        }
+
+        Ok((index_writes, index_removals))
+    }
+
+    // Partitions staged rows into brand-new inserts and updates against existing rows,
+    // per the ON CONFLICT clause; DO NOTHING rows are dropped from the batch entirely.
+    fn resolve_on_conflict(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        all_columns: &[ColumnDefinition],
+        on_conflict: &OnConflict,
+        staged: Vec<(Vec<u8>, Vec<Datum>)>,
+    ) -> Result<Vec<(Vec<u8>, Vec<Datum>)>, protocol::results::QueryError> {
+        let mut identity_indices = vec![];
+        for target in &on_conflict.target_columns {
+            match all_columns.iter().position(|c| c.has_name(target)) {
+                Some(index) => identity_indices.push(index),
+                None => {
+                    return Err(QueryErrorBuilder::new().column_does_not_exist(vec![target.clone()]).build());
+                }
+            }
+        }
+
+        // The conflict target has to name a unique/primary key constraint, not just any
+        // existing columns, or there is no way to detect a conflicting row.
+        let indexes = (self.storage.lock().unwrap())
+            .table_indexes(schema_name, table_name)
+            .map_err(|e| QueryErrorBuilder::new().syntax_error(e.to_string()).build())?;
+        let target_set: std::collections::HashSet<usize> = identity_indices.iter().copied().collect();
+        let targets_a_unique_index = indexes
+            .iter()
+            .any(|index| index.unique && index.key_columns.iter().copied().collect::<std::collections::HashSet<_>>() == target_set);
+        if !targets_a_unique_index {
+            return Err(QueryErrorBuilder::new()
+                .invalid_conflict_target(&on_conflict.target_columns)
+                .build());
+        }
+
+        let existing = (self.storage.lock().unwrap())
+            .table_scan(schema_name, table_name)
+            .map_err(|e| QueryErrorBuilder::new().syntax_error(e.to_string()).build())?;
+        let mut existing_by_identity = std::collections::HashMap::new();
+        for (key, packed) in existing {
+            let row = Binary::unpack(&packed);
+            let identity: Vec<Datum> = identity_indices.iter().map(|i| row[*i].clone()).collect();
+            existing_by_identity.insert(identity, (key.into_bytes(), row));
+        }
+
+        let mut resolved = vec![];
+        let mut claimed_keys = std::collections::HashSet::new();
+        for (key, record) in staged {
+            let identity: Vec<Datum> = identity_indices.iter().map(|i| record[*i].clone()).collect();
+            match existing_by_identity.get(&identity) {
+                None => resolved.push((key, record)),
+                Some((existing_key, existing_row)) => match &on_conflict.action {
+                    OnConflictAction::DoNothing => {}
+                    OnConflictAction::DoUpdate(assignments) => {
+                        if !claimed_keys.insert(existing_key.clone()) {
+                            return Err(QueryErrorBuilder::new()
+                                .syntax_error("ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned())
+                                .build());
+                        }
+
+                        let evaluation = ExpressionEvaluation::new(self.session.clone());
+                        let mut updated = existing_row.clone();
+                        for (column_name, expr) in assignments {
+                            let index = match all_columns.iter().position(|c| c.has_name(column_name)) {
+                                Some(index) => index,
+                                None => {
+                                    return Err(QueryErrorBuilder::new()
+                                        .column_does_not_exist(vec![column_name.clone()])
+                                        .build());
+                                }
+                            };
+                            let value = resolve_conflict_expr(expr, existing_row, &record, all_columns, &evaluation).map_err(|_| {
+                                QueryErrorBuilder::new()
+                                    .syntax_error(format!("cannot evaluate ON CONFLICT DO UPDATE assignment for {}", column_name))
+                                    .build()
+                            })?;
+                            updated[index] = value;
+                        }
+                        resolved.push((existing_key.clone(), updated));
+                    }
+                },
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+// Resolves a `SET column = expr` assignment: `EXCLUDED.col` reads the proposed row, a
+// bare column reads the existing row, anything else falls back to the general evaluator.
+fn resolve_conflict_expr(
+    expr: &Expr,
+    existing_row: &[Datum],
+    excluded_row: &[Datum],
+    all_columns: &[ColumnDefinition],
+    evaluation: &ExpressionEvaluation,
+) -> Result<Datum, ()> {
+    match expr {
+        Expr::CompoundIdentifier(parts) if parts.len() == 2 && parts[0].value.eq_ignore_ascii_case("excluded") => all_columns
+            .iter()
+            .position(|c| c.has_name(&parts[1].value))
+            .map(|index| excluded_row[index].clone())
+            .ok_or(()),
+        Expr::Identifier(ident) => all_columns
+            .iter()
+            .position(|c| c.has_name(&ident.value))
+            .map(|index| existing_row[index].clone())
+            .ok_or(()),
+        _ => evaluation.eval(expr).and_then(|value| Datum::try_from(&value).map_err(|_| ())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol::results::QueryResult;
+    use sqlparser::{ast::Statement, dialect::GenericDialect, parser::Parser};
+    use sql_types::SqlType;
+    use storage::IndexDefinition;
+
+    struct TestBackend;
+
+    impl BackendStorage for TestBackend {}
+
+    struct TestSender {
+        sent: Mutex<Vec<QueryResult>>,
+    }
+
+    impl TestSender {
+        fn new() -> TestSender {
+            TestSender { sent: Mutex::new(vec![]) }
+        }
+    }
+
+    impl Sender for TestSender {
+        fn send(&self, result: QueryResult) -> Result<(), ()> {
+            self.sent.lock().unwrap().push(result);
+            Ok(())
+        }
+    }
+
+    // `TableInserts::input` is never read by the functions these tests call directly
+    // (insert_rows_from_source, resolve_on_conflict); a parsed no-op query satisfies
+    // the field without guessing at sqlparser's internal AST shape.
+    fn dummy_query() -> Box<Query> {
+        match Parser::parse_sql(&GenericDialect {}, "SELECT 1")
+            .expect("dummy query must parse")
+            .into_iter()
+            .next()
+        {
+            Some(Statement::Query(query)) => query,
+            _ => panic!("expected a query statement"),
+        }
+    }
+
+    fn new_command<'a>(
+        storage: Arc<Mutex<FrontendStorage<TestBackend>>>,
+        session: Arc<dyn Sender>,
+        on_conflict: Option<OnConflict>,
+    ) -> InsertCommand<'a, TestBackend> {
+        InsertCommand::new(
+            "test",
+            TableInserts {
+                table_id: crate::query::plan::TableId::new("public", "t"),
+                column_indices: vec![],
+                input: dummy_query(),
+            },
+            on_conflict,
+            vec![],
+            storage,
+            session,
+        )
+    }
+
+    #[test]
+    fn dictionary_round_trips_repeated_values_to_the_same_code() {
+        let mut dictionary = ColumnDictionary {
+            code_by_value: HashMap::new(),
+            value_by_code: vec![],
+        };
+
+        let red = dictionary.code_for("red");
+        let blue = dictionary.code_for("blue");
+        let red_again = dictionary.code_for("red");
+
+        assert_eq!(red, red_again);
+        assert_ne!(red, blue);
+        assert_eq!(dictionary.value_by_code[red as usize], "red");
+        assert_eq!(dictionary.value_by_code[blue as usize], "blue");
+    }
+
+    #[test]
+    fn insert_from_source_fills_default_for_a_column_missing_from_the_select_list() {
+        let storage = Arc::new(Mutex::new(FrontendStorage::<TestBackend>::default()));
+        storage.lock().unwrap().create_schema("public");
+        storage.lock().unwrap().create_table(
+            "public",
+            "t",
+            vec![
+                ColumnDefinition::new("id", SqlType::Integer, false, None, false),
+                ColumnDefinition::new(
+                    "status",
+                    SqlType::VarChar(16),
+                    false,
+                    Some(Expr::Value(Value::SingleQuotedString("active".to_owned()))),
+                    false,
+                ),
+            ],
+            vec![],
+        );
+
+        let session: Arc<dyn Sender> = Arc::new(TestSender::new());
+        let mut command = new_command(storage.clone(), session.clone(), None);
+
+        command
+            .insert_rows_from_source(
+                "public",
+                "t",
+                vec!["id".to_owned()],
+                vec![vec![Datum::from_i64(1)]],
+            )
+            .expect("insert should not fail");
+
+        let rows = storage.lock().unwrap().table_scan("public", "t").unwrap();
+        assert_eq!(rows.len(), 1);
+        let row = Binary::unpack(&rows[0].1);
+        assert_eq!(row[0], Datum::from_i64(1));
+        assert_eq!(row[1], Datum::from_string("active".to_owned()));
+    }
+
+    #[test]
+    fn insert_from_source_reports_not_null_violation_with_no_value_or_default() {
+        let storage = Arc::new(Mutex::new(FrontendStorage::<TestBackend>::default()));
+        storage.lock().unwrap().create_schema("public");
+        storage.lock().unwrap().create_table(
+            "public",
+            "t",
+            vec![
+                ColumnDefinition::new("id", SqlType::Integer, false, None, false),
+                ColumnDefinition::new("name", SqlType::VarChar(16), false, None, false),
+            ],
+            vec![],
+        );
+
+        let sender = Arc::new(TestSender::new());
+        let session: Arc<dyn Sender> = sender.clone();
+        let mut command = new_command(storage.clone(), session, None);
+
+        command
+            .insert_rows_from_source("public", "t", vec!["id".to_owned()], vec![vec![Datum::from_i64(2)]])
+            .expect("insert should not return a system error");
+
+        assert!(storage.lock().unwrap().table_scan("public", "t").unwrap().is_empty());
+        assert_eq!(
+            sender.sent.lock().unwrap().last(),
+            Some(&Err(QueryErrorBuilder::new().not_null_violation("name", 1).build()))
+        );
+    }
+
+    #[test]
+    fn on_conflict_do_update_rejects_a_second_match_against_the_same_row() {
+        let storage = Arc::new(Mutex::new(FrontendStorage::<TestBackend>::default()));
+        storage.lock().unwrap().create_schema("public");
+        storage.lock().unwrap().create_table(
+            "public",
+            "t",
+            vec![
+                ColumnDefinition::new("id", SqlType::Integer, false, None, false),
+                ColumnDefinition::new("val", SqlType::VarChar(16), true, None, false),
+            ],
+            vec![IndexDefinition {
+                name: "t_id_key".to_owned(),
+                key_columns: vec![0],
+                include_columns: vec![1],
+                unique: true,
+            }],
+        );
+        storage
+            .lock()
+            .unwrap()
+            .insert_into(
+                "public",
+                "t",
+                vec![(
+                    Binary::with_data(vec![1, 2, 3]),
+                    Binary::pack(&[Datum::from_i64(5), Datum::from_string("orig".to_owned())]),
+                )],
+            )
+            .unwrap();
+
+        let session: Arc<dyn Sender> = Arc::new(TestSender::new());
+        let on_conflict = OnConflict {
+            target_columns: vec!["id".to_owned()],
+            action: OnConflictAction::DoUpdate(vec![(
+                "val".to_owned(),
+                Expr::Value(Value::SingleQuotedString("updated".to_owned())),
+            )]),
+        };
+        let command = new_command(storage.clone(), session, Some(on_conflict));
+        let all_columns = storage.lock().unwrap().table_columns("public", "t").unwrap();
+        let on_conflict = command.on_conflict.as_ref().unwrap();
+
+        let staged = vec![
+            (vec![10], vec![Datum::from_i64(5), Datum::from_string("new1".to_owned())]),
+            (vec![20], vec![Datum::from_i64(5), Datum::from_string("new2".to_owned())]),
+        ];
+
+        let result = command.resolve_on_conflict("public", "t", &all_columns, on_conflict, staged);
+
+        assert_eq!(
+            result,
+            Err(QueryErrorBuilder::new()
+                .syntax_error("ON CONFLICT DO UPDATE command cannot affect row a second time".to_owned())
+                .build())
+        );
+    }
 }
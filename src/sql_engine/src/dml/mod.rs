@@ -0,0 +1,55 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(crate) mod insert;
+
+use crate::query::plan::TableInserts;
+use insert::{InsertCommand, OnConflict};
+use kernel::SystemResult;
+use protocol::{bind::BoundParameter, Sender};
+use sqlparser::ast::{Expr, Value};
+use std::sync::{Arc, Mutex};
+use storage::{backend::BackendStorage, frontend::FrontendStorage};
+
+// Evaluates the expressions a DML statement can carry outside of its literal VALUES.
+// Only a plain literal (`Expr::Value`) is evaluated; any other expression yields `Err(())`.
+pub(crate) struct ExpressionEvaluation {
+    #[allow(dead_code)]
+    session: Arc<dyn Sender>,
+}
+
+impl ExpressionEvaluation {
+    pub(crate) fn new(session: Arc<dyn Sender>) -> ExpressionEvaluation {
+        ExpressionEvaluation { session }
+    }
+
+    pub(crate) fn eval(&self, expr: &Expr) -> Result<Value, ()> {
+        match expr {
+            Expr::Value(value) => Ok(value.clone()),
+            _ => Err(()),
+        }
+    }
+}
+
+// Entry point the planner calls for an INSERT statement.
+pub(crate) fn execute_insert<P: BackendStorage>(
+    raw_sql_query: &str,
+    table_inserts: TableInserts,
+    on_conflict: Option<OnConflict>,
+    parameters: Vec<BoundParameter>,
+    storage: Arc<Mutex<FrontendStorage<P>>>,
+    session: Arc<dyn Sender>,
+) -> SystemResult<()> {
+    InsertCommand::new(raw_sql_query, table_inserts, on_conflict, parameters, storage, session).execute()
+}
@@ -0,0 +1,149 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::Sender;
+use representation::{Binary, Datum};
+use sqlparser::ast::{Ident, Query, Select, SelectItem, TableFactor};
+use std::sync::{Arc, Mutex};
+use storage::{backend::BackendStorage, frontend::FrontendStorage};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TableId {
+    schema_name: String,
+    name: String,
+}
+
+impl TableId {
+    pub(crate) fn new(schema_name: &str, name: &str) -> TableId {
+        TableId {
+            schema_name: schema_name.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    pub(crate) fn schema_name(&self) -> &str {
+        &self.schema_name
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+pub(crate) struct TableInserts {
+    pub(crate) table_id: TableId,
+    pub(crate) column_indices: Vec<Ident>,
+    pub(crate) input: Box<Query>,
+}
+
+// The source side of `INSERT INTO t SELECT ...`: a projection-only, unfiltered scan.
+pub(crate) struct SelectInput {
+    select: Box<Select>,
+}
+
+impl SelectInput {
+    pub(crate) fn new(select: Box<Select>) -> SelectInput {
+        SelectInput { select }
+    }
+
+    pub(crate) fn execute<P: BackendStorage>(
+        &self,
+        storage: Arc<Mutex<FrontendStorage<P>>>,
+        _session: Arc<dyn Sender>,
+    ) -> Result<Vec<Vec<Datum>>, ()> {
+        // This is a projection-only, unfiltered scan: it has no way to honor a WHERE/
+        // GROUP BY/HAVING or a join, so reject rather than silently return every row.
+        if self.select.selection.is_some() || !self.select.group_by.is_empty() || self.select.having.is_some() {
+            return Err(());
+        }
+
+        let (schema_name, table_name) = self.source_table()?;
+
+        let all_columns = (storage.lock().unwrap())
+            .table_columns(&schema_name, &table_name)
+            .map_err(|_| ())?;
+
+        let projected_indices: Vec<usize> = if self
+            .select
+            .projection
+            .iter()
+            .any(|item| matches!(item, SelectItem::Wildcard))
+        {
+            (0..all_columns.len()).collect()
+        } else {
+            let mut indices = vec![];
+            for item in &self.select.projection {
+                let name = match item {
+                    SelectItem::UnnamedExpr(sqlparser::ast::Expr::Identifier(ident)) => ident.value.clone(),
+                    SelectItem::ExprWithAlias {
+                        expr: sqlparser::ast::Expr::Identifier(ident),
+                        ..
+                    } => ident.value.clone(),
+                    _ => return Err(()),
+                };
+                let index = all_columns.iter().position(|c| c.has_name(&name)).ok_or(())?;
+                indices.push(index);
+            }
+            indices
+        };
+
+        // Reverse any dictionary-encoded projected column back to its string value;
+        // callers (e.g. INSERT ... SELECT) expect real values, not internal codes.
+        let mut dictionaries = std::collections::HashMap::new();
+        for &index in &projected_indices {
+            if all_columns[index].is_dictionary_encoded() {
+                let (_, value_by_code) = (storage.lock().unwrap())
+                    .load_dictionary(&schema_name, &table_name, all_columns[index].name())
+                    .map_err(|_| ())?;
+                dictionaries.insert(index, value_by_code);
+            }
+        }
+
+        let rows = (storage.lock().unwrap()).table_scan(&schema_name, &table_name).map_err(|_| ())?;
+
+        rows.into_iter()
+            .map(|(_, packed)| {
+                let full_row = Binary::unpack(&packed);
+                projected_indices
+                    .iter()
+                    .map(|i| match (&full_row[*i], dictionaries.get(i)) {
+                        (Datum::DictCode(code), Some(value_by_code)) => {
+                            value_by_code.get(*code as usize).cloned().map(Datum::from_string).ok_or(())
+                        }
+                        _ => Ok(full_row[*i].clone()),
+                    })
+                    .collect::<Result<Vec<Datum>, ()>>()
+            })
+            .collect()
+    }
+
+    fn source_table(&self) -> Result<(String, String), ()> {
+        if self.select.from.len() != 1 || !self.select.from[0].joins.is_empty() {
+            return Err(());
+        }
+
+        let relation = self.select.from.get(0).ok_or(())?;
+        match &relation.relation {
+            TableFactor::Table { name, .. } => {
+                let mut parts = name.0.iter().map(|ident| ident.value.clone());
+                match (parts.next(), parts.next()) {
+                    (Some(schema), Some(table)) => Ok((schema, table)),
+                    (Some(table), None) => Ok(("public".to_owned(), table)),
+                    _ => Err(()),
+                }
+            }
+            _ => Err(()),
+        }
+    }
+}
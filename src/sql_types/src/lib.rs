@@ -0,0 +1,98 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use representation::Datum;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintError {
+    OutOfRange,
+    TypeMismatch(String),
+    ValueTooLong(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SqlTypeConstraint {
+    sql_type: SqlType,
+}
+
+impl SqlTypeConstraint {
+    pub fn validate(&self, value: &str) -> Result<(), ConstraintError> {
+        match self.sql_type {
+            SqlType::SmallInt | SqlType::Integer | SqlType::BigInt => value
+                .parse::<i64>()
+                .map(|_| ())
+                .map_err(|_| ConstraintError::TypeMismatch(value.to_owned())),
+            SqlType::Real | SqlType::DoublePrecision => value
+                .parse::<f64>()
+                .map(|_| ())
+                .map_err(|_| ConstraintError::TypeMismatch(value.to_owned())),
+            SqlType::Bool => value
+                .parse::<bool>()
+                .map(|_| ())
+                .map_err(|_| ConstraintError::TypeMismatch(value.to_owned())),
+            SqlType::VarChar(max_len) | SqlType::Char(max_len) => {
+                if value.len() as u64 > max_len {
+                    Err(ConstraintError::ValueTooLong(max_len))
+                } else {
+                    Ok(())
+                }
+            }
+            SqlType::Date | SqlType::Time | SqlType::Timestamp => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PostgreSqlType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlType {
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    DoublePrecision,
+    Bool,
+    Date,
+    Time,
+    Timestamp,
+    VarChar(u64),
+    Char(u64),
+}
+
+impl SqlType {
+    pub fn constraint(&self) -> SqlTypeConstraint {
+        SqlTypeConstraint { sql_type: *self }
+    }
+
+    pub fn to_pg_types(&self) -> PostgreSqlType {
+        PostgreSqlType
+    }
+
+    // Decodes a value bound via the Bind step into the Datum this column stores. The bytes
+    // are always read as UTF-8 text; binary-format integers, floats and booleans are not decoded.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Datum, ()> {
+        let text = std::str::from_utf8(bytes).map_err(|_| ())?;
+        match self {
+            SqlType::SmallInt | SqlType::Integer | SqlType::BigInt => {
+                text.parse::<i64>().map(Datum::from_i64).map_err(|_| ())
+            }
+            SqlType::Real | SqlType::DoublePrecision => text.parse::<f64>().map(Datum::from_f64).map_err(|_| ()),
+            SqlType::Bool => text.parse::<bool>().map(Datum::from_bool).map_err(|_| ()),
+            SqlType::VarChar(_) | SqlType::Char(_) | SqlType::Date | SqlType::Time | SqlType::Timestamp => {
+                Ok(Datum::from_string(text.to_owned()))
+            }
+        }
+    }
+}
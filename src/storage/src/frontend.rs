@@ -0,0 +1,169 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{backend::BackendStorage, ColumnDefinition, IndexDefinition, Row};
+use kernel::{SystemError, SystemResult};
+use std::{collections::HashMap, marker::PhantomData};
+
+struct Table {
+    columns: Vec<ColumnDefinition>,
+    rows: Vec<Row>,
+    indexes: Vec<IndexDefinition>,
+    index_rows: HashMap<String, Vec<Row>>,
+    dictionaries: HashMap<String, (HashMap<String, u32>, Vec<String>)>,
+}
+
+pub struct FrontendStorage<P: BackendStorage> {
+    schemas: HashMap<String, HashMap<String, Table>>,
+    next_key: u64,
+    backend: PhantomData<P>,
+}
+
+impl<P: BackendStorage> FrontendStorage<P> {
+    pub fn default() -> FrontendStorage<P> {
+        FrontendStorage {
+            schemas: HashMap::new(),
+            next_key: 0,
+            backend: PhantomData,
+        }
+    }
+
+    pub fn create_schema(&mut self, schema_name: &str) {
+        self.schemas.entry(schema_name.to_owned()).or_insert_with(HashMap::new);
+    }
+
+    pub fn create_table(&mut self, schema_name: &str, table_name: &str, columns: Vec<ColumnDefinition>, indexes: Vec<IndexDefinition>) {
+        self.schemas.entry(schema_name.to_owned()).or_insert_with(HashMap::new).insert(
+            table_name.to_owned(),
+            Table {
+                columns,
+                rows: vec![],
+                indexes,
+                index_rows: HashMap::new(),
+                dictionaries: HashMap::new(),
+            },
+        );
+    }
+
+    pub fn schema_exists(&self, schema_name: &str) -> bool {
+        self.schemas.contains_key(schema_name)
+    }
+
+    pub fn table_exists(&self, schema_name: &str, table_name: &str) -> bool {
+        self.schemas.get(schema_name).map(|tables| tables.contains_key(table_name)).unwrap_or(false)
+    }
+
+    pub fn table_columns(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<ColumnDefinition>> {
+        self.table(schema_name, table_name).map(|table| table.columns.clone())
+    }
+
+    pub fn table_indexes(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<IndexDefinition>> {
+        self.table(schema_name, table_name).map(|table| table.indexes.clone())
+    }
+
+    pub fn table_scan(&self, schema_name: &str, table_name: &str) -> SystemResult<Vec<Row>> {
+        self.table(schema_name, table_name).map(|table| table.rows.clone())
+    }
+
+    pub fn next_key_id(&mut self) -> u64 {
+        let key = self.next_key;
+        self.next_key += 1;
+        key
+    }
+
+    pub fn load_dictionary(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        column_name: &str,
+    ) -> SystemResult<(HashMap<String, u32>, Vec<String>)> {
+        let table = self.table(schema_name, table_name)?;
+        Ok(table.dictionaries.get(column_name).cloned().unwrap_or_default())
+    }
+
+    pub fn flush_dictionary(
+        &mut self,
+        schema_name: &str,
+        table_name: &str,
+        column_name: &str,
+        value_by_code: &[String],
+    ) -> SystemResult<()> {
+        let table = self.table_mut(schema_name, table_name)?;
+        let code_by_value = value_by_code
+            .iter()
+            .enumerate()
+            .map(|(code, value)| (value.clone(), code as u32))
+            .collect();
+        table
+            .dictionaries
+            .insert(column_name.to_owned(), (code_by_value, value_by_code.to_vec()));
+        Ok(())
+    }
+
+    pub fn insert_into(&mut self, schema_name: &str, table_name: &str, rows: Vec<Row>) -> SystemResult<usize> {
+        self.insert_into_with_indexes(schema_name, table_name, rows, vec![], vec![])
+    }
+
+    // index_removals deletes stale index entries before index_writes is applied.
+    pub fn insert_into_with_indexes(
+        &mut self,
+        schema_name: &str,
+        table_name: &str,
+        rows: Vec<Row>,
+        index_writes: Vec<(String, Vec<Row>)>,
+        index_removals: Vec<(String, Vec<u8>)>,
+    ) -> SystemResult<usize> {
+        let inserted = rows.len();
+        let table = self.table_mut(schema_name, table_name)?;
+
+        for (key, value) in rows {
+            match table.rows.iter_mut().find(|(existing_key, _)| existing_key == &key) {
+                Some(slot) => *slot = (key, value),
+                None => table.rows.push((key, value)),
+            }
+        }
+
+        for (index_name, stale_key) in index_removals {
+            if let Some(index_rows) = table.index_rows.get_mut(&index_name) {
+                index_rows.retain(|(existing_key, _)| existing_key.as_bytes() != stale_key.as_slice());
+            }
+        }
+
+        for (index_name, entries) in index_writes {
+            let index_rows = table.index_rows.entry(index_name).or_insert_with(Vec::new);
+            for (key, value) in entries {
+                match index_rows.iter_mut().find(|(existing_key, _)| existing_key == &key) {
+                    Some(slot) => *slot = (key, value),
+                    None => index_rows.push((key, value)),
+                }
+            }
+        }
+
+        Ok(inserted)
+    }
+
+    fn table(&self, schema_name: &str, table_name: &str) -> SystemResult<&Table> {
+        self.schemas
+            .get(schema_name)
+            .and_then(|tables| tables.get(table_name))
+            .ok_or_else(|| SystemError::unrecoverable(format!("table {}.{} does not exist", schema_name, table_name)))
+    }
+
+    fn table_mut(&mut self, schema_name: &str, table_name: &str) -> SystemResult<&mut Table> {
+        self.schemas
+            .get_mut(schema_name)
+            .and_then(|tables| tables.get_mut(table_name))
+            .ok_or_else(|| SystemError::unrecoverable(format!("table {}.{} does not exist", schema_name, table_name)))
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2020 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod backend;
+pub mod frontend;
+
+use sqlparser::ast::Expr;
+use sql_types::SqlType;
+
+pub type Row = (representation::Binary, representation::Binary);
+
+// unique also covers the primary key; it's what ON CONFLICT targets are validated against.
+#[derive(Debug, Clone)]
+pub struct IndexDefinition {
+    pub name: String,
+    pub key_columns: Vec<usize>,
+    pub include_columns: Vec<usize>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnDefinition {
+    name: String,
+    sql_type: SqlType,
+    nullable: bool,
+    default: Option<Expr>,
+    dictionary_encoded: bool,
+}
+
+impl ColumnDefinition {
+    pub fn new(name: &str, sql_type: SqlType, nullable: bool, default: Option<Expr>, dictionary_encoded: bool) -> ColumnDefinition {
+        ColumnDefinition {
+            name: name.to_owned(),
+            sql_type,
+            nullable,
+            default,
+            dictionary_encoded,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name
+    }
+
+    pub fn sql_type(&self) -> SqlType {
+        self.sql_type
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    pub fn default_value(&self) -> Option<&Expr> {
+        self.default.as_ref()
+    }
+
+    pub fn is_dictionary_encoded(&self) -> bool {
+        self.dictionary_encoded
+    }
+}